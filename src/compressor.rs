@@ -0,0 +1,244 @@
+use std::{
+    cell::{Cell, RefCell},
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use crate::{Controlled, Filter, Frame, Signal};
+
+/// Tracks the maximum of the last `len` amplitudes pushed to it
+///
+/// Backed by a power-of-two-sized max-reduce tree: leaves hold recent per-sample amplitudes and
+/// each internal node holds the max of its two children, so pushing a new amplitude updates the
+/// tree in O(log n) and the window's current maximum is always available at the root. The tree is
+/// padded up to `tree_capacity`, the next power of two at or above `len`; the padding leaves are
+/// never written to (amplitudes are always non-negative, so they can't win the max), keeping the
+/// tracked window exactly `len` samples rather than silently widening it to the padded size.
+struct PeakWindow {
+    tree: Box<[f32]>,
+    tree_capacity: usize,
+    len: usize,
+    cursor: usize,
+}
+
+impl PeakWindow {
+    fn new(len: usize) -> Self {
+        let len = len.max(1);
+        let tree_capacity = len.next_power_of_two();
+        Self {
+            tree: vec![0.0; 2 * tree_capacity - 1].into_boxed_slice(),
+            tree_capacity,
+            len,
+            cursor: 0,
+        }
+    }
+
+    fn push(&mut self, amplitude: f32) {
+        let mut i = self.tree_capacity - 1 + self.cursor;
+        self.tree[i] = amplitude;
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            self.tree[parent] = self.tree[2 * parent + 1].max(self.tree[2 * parent + 2]);
+            i = parent;
+        }
+        self.cursor = (self.cursor + 1) % self.len;
+    }
+
+    fn max(&self) -> f32 {
+        self.tree[0]
+    }
+}
+
+fn load_f32(cell: &AtomicU32) -> f32 {
+    f32::from_bits(cell.load(Ordering::Relaxed))
+}
+
+fn store_f32(cell: &AtomicU32, value: f32) {
+    cell.store(value.to_bits(), Ordering::Relaxed);
+}
+
+/// A dynamics processor: reduces gain above `threshold` to compress or limit dynamic range
+///
+/// Wraps any mono or stereo [`Signal`]. A high `ratio` (e.g. 20 or above) behaves as a limiter;
+/// lower ratios give gentler compression. Driven by a windowed peak detector feeding a
+/// one-pole gain follower with separate attack and release times.
+pub struct Compressor<T> {
+    inner: T,
+    window: RefCell<PeakWindow>,
+    gain: Cell<f32>,
+    threshold: AtomicU32,
+    ratio: AtomicU32,
+    attack: AtomicU32,
+    release: AtomicU32,
+}
+
+impl<T> Compressor<T> {
+    /// Wrap `signal` in a dynamics processor whose peak detector looks back over
+    /// `window_samples` samples
+    pub(crate) fn new(signal: T, window_samples: usize) -> Self {
+        Self {
+            inner: signal,
+            window: RefCell::new(PeakWindow::new(window_samples)),
+            gain: Cell::new(1.0),
+            threshold: AtomicU32::new(1.0f32.to_bits()),
+            ratio: AtomicU32::new(1.0f32.to_bits()),
+            attack: AtomicU32::new(0.01f32.to_bits()),
+            release: AtomicU32::new(0.1f32.to_bits()),
+        }
+    }
+}
+
+impl<T: Signal> Signal for Compressor<T>
+where
+    T::Frame: Frame + Copy,
+{
+    type Frame = T::Frame;
+
+    fn sample(&self, interval: f32, out: &mut [T::Frame]) {
+        self.inner.sample(interval, out);
+
+        let threshold = load_f32(&self.threshold);
+        let ratio = load_f32(&self.ratio);
+        let attack = (-interval / load_f32(&self.attack)).exp();
+        let release = (-interval / load_f32(&self.release)).exp();
+
+        let mut window = self.window.borrow_mut();
+        let mut gain = self.gain.get();
+        for frame in out.iter_mut() {
+            let level = frame
+                .channels()
+                .iter()
+                .fold(0.0f32, |max, &x| max.max(x.abs()));
+            window.push(level);
+            let peak = window.max();
+
+            let target = if peak <= threshold || peak == 0.0 {
+                1.0
+            } else {
+                (peak / threshold).powf(1.0 / ratio - 1.0)
+            };
+            let coeff = if target < gain { attack } else { release };
+            gain = target + (gain - target) * coeff;
+
+            for x in frame.channels_mut() {
+                *x *= gain;
+            }
+        }
+        self.gain.set(gain);
+    }
+
+    fn remaining(&self) -> f32 {
+        self.inner.remaining()
+    }
+}
+
+impl<T> Filter for Compressor<T> {
+    type Inner = T;
+    fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+/// Thread-safe control for a [`Compressor`] filter
+#[derive(Copy, Clone)]
+pub struct CompressorControl<'a, T>(&'a Compressor<T>);
+
+unsafe impl<'a, T: 'a> Controlled<'a> for Compressor<T> {
+    type Control = CompressorControl<'a, T>;
+
+    unsafe fn make_control(signal: &'a Compressor<T>) -> Self::Control {
+        CompressorControl(signal)
+    }
+}
+
+impl<'a, T> CompressorControl<'a, T> {
+    /// Level, in the same units as frame amplitudes, above which gain reduction begins
+    pub fn threshold(&self) -> f32 {
+        load_f32(&self.0.threshold)
+    }
+
+    /// Set the level above which gain reduction begins
+    pub fn set_threshold(&self, threshold: f32) {
+        store_f32(&self.0.threshold, threshold);
+    }
+
+    /// Ratio of input to output dB above `threshold`; higher values limit more aggressively
+    pub fn ratio(&self) -> f32 {
+        load_f32(&self.0.ratio)
+    }
+
+    /// Set the ratio of input to output dB above `threshold`
+    pub fn set_ratio(&self, ratio: f32) {
+        store_f32(&self.0.ratio, ratio);
+    }
+
+    /// Time constant, in seconds, over which gain reduction is applied once the peak rises
+    pub fn attack(&self) -> f32 {
+        load_f32(&self.0.attack)
+    }
+
+    /// Set the attack time constant, in seconds
+    pub fn set_attack(&self, attack: f32) {
+        store_f32(&self.0.attack, attack);
+    }
+
+    /// Time constant, in seconds, over which gain reduction relaxes once the peak falls
+    pub fn release(&self) -> f32 {
+        load_f32(&self.0.release)
+    }
+
+    /// Set the release time constant, in seconds
+    pub fn set_release(&self, release: f32) {
+        store_f32(&self.0.release, release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Frames, FramesSignal};
+
+    #[test]
+    fn peak_window_tracks_max() {
+        let mut window = PeakWindow::new(4);
+        for x in [0.1, 0.9, 0.2, 0.3] {
+            window.push(x);
+        }
+        assert_eq!(window.max(), 0.9);
+        // pushing past capacity evicts the oldest amplitude (0.1), leaving 0.9 as the max
+        window.push(0.05);
+        assert_eq!(window.max(), 0.9);
+        // evicts 0.9
+        window.push(0.05);
+        window.push(0.05);
+        assert_eq!(window.max(), 0.3);
+    }
+
+    #[test]
+    fn peak_window_tracks_exact_requested_length_not_padded_capacity() {
+        // len=5 pads to a tree_capacity of 8, but the window must still forget the 0.9 after
+        // exactly 5 more pushes, not 8.
+        let mut window = PeakWindow::new(5);
+        window.push(0.9);
+        for _ in 0..4 {
+            window.push(0.1);
+        }
+        assert_eq!(window.max(), 0.9);
+        window.push(0.1);
+        assert_eq!(window.max(), 0.1);
+    }
+
+    #[test]
+    fn reduces_gain_above_threshold() {
+        let data = Frames::from_slice(1, &[1.0f32, 1.0, 1.0, 1.0]);
+        let signal = FramesSignal::from(data);
+        let compressor = Compressor::new(signal, 4);
+        let control = unsafe { Compressor::make_control(&compressor) };
+        control.set_threshold(0.5);
+        control.set_ratio(4.0);
+        control.set_attack(0.001);
+
+        let mut buf = [0.0f32; 4];
+        compressor.sample(1.0, &mut buf);
+        assert!(buf[3] < 1.0);
+    }
+}
@@ -1,32 +1,76 @@
-use std::{cell::Cell, sync::Arc};
+use std::{
+    cell::{Cell, RefCell},
+    f32::consts::FRAC_PI_2,
+    sync::Arc,
+};
 
-use crate::{frame, Frame, Frames, Signal};
+use crate::{Frame, Frames, InterpolationMode, Signal};
 
 /// Loops [`Frames`] end-to-end to construct a repeating signal
 pub struct Cycle<T> {
     /// Current playback time, in samples
     cursor: Cell<f32>,
     frames: Arc<Frames<T>>,
+    interpolation: RefCell<InterpolationMode>,
+    /// Length, in samples, of the crossfade applied across the loop seam, or 0 for none
+    fade: f32,
 }
 
 impl<T> Cycle<T> {
     /// Construct cycle from `frames`
-    // TODO: Crossfade
     pub fn new(frames: Arc<Frames<T>>) -> Self {
+        Self::with_crossfade(frames, 0)
+    }
+
+    /// Construct a cycle that crossfades the final `fade_samples` of each loop iteration with
+    /// the samples following its start, hiding the seam in loops that don't begin and end in
+    /// phase
+    pub fn with_crossfade(frames: Arc<Frames<T>>, fade_samples: usize) -> Self {
         Self {
             cursor: Cell::new(0.0),
+            interpolation: RefCell::new(InterpolationMode::default()),
+            fade: fade_samples as f32,
             frames,
         }
     }
 
+    /// Change the interpolation used to reconstruct samples between stored frames
+    pub fn set_interpolation_mode(&self, mode: InterpolationMode) {
+        *self.interpolation.borrow_mut() = mode;
+    }
+
     /// Interpolate a frame for position `sample`
     fn interpolate(&self, sample: f32) -> T
     where
-        T: Frame,
+        T: Frame + Copy,
     {
-        let a = sample as usize;
-        let b = (a + 1) % self.frames.len();
-        frame::lerp(&self.frames[a], &self.frames[b], sample.fract())
+        let len_samples = self.frames.len() as isize;
+        let len = self.frames.len() as f32;
+        let mode = self.interpolation.borrow().clone();
+        let fetch = |sample: f32| {
+            mode.clone().interpolate(sample.fract(), |delta| {
+                let idx = (sample as isize + delta).rem_euclid(len_samples) as usize;
+                self.frames[idx]
+            })
+        };
+
+        let out = fetch(sample);
+        if self.fade == 0.0 || sample < len - self.fade {
+            return out;
+        }
+
+        // Within `fade` samples of the seam: mix the tail (fading out) with the head of the next
+        // iteration (fading in) using an equal-power curve so loudness stays constant.
+        let progress = (sample - (len - self.fade)) / self.fade;
+        let fade_out = (progress * FRAC_PI_2).cos();
+        let fade_in = (progress * FRAC_PI_2).sin();
+        let head = fetch(sample - (len - self.fade));
+
+        let mut result = out;
+        for i in 0..result.channels().len() {
+            result.channels_mut()[i] = out.channels()[i] * fade_out + head.channels()[i] * fade_in;
+        }
+        result
     }
 }
 
@@ -43,6 +87,105 @@ impl<T: Frame + Copy> Signal for Cycle<T> {
     }
 }
 
+/// Plays an intro once, then loops the region between `loop_start` and `loop_end` forever
+///
+/// Useful for music assets made of a one-shot intro followed by a loop body, avoiding the need
+/// to pre-duplicate the loop body into its own buffer.
+pub struct Loop<T> {
+    /// Current playback time, in samples
+    cursor: Cell<f64>,
+    /// Start of the repeating region, in samples
+    loop_start: f64,
+    /// End of the repeating region, in samples; playback wraps back to `loop_start` here
+    loop_end: f64,
+    frames: Arc<Frames<T>>,
+    interpolation: RefCell<InterpolationMode>,
+}
+
+impl<T: Frame + Copy> Loop<T> {
+    /// Construct a loop from `frames`, playing the intro once before repeating the region
+    /// between `loop_start_seconds` and `loop_end_seconds` forever
+    pub fn new(frames: Arc<Frames<T>>, loop_start_seconds: f64, loop_end_seconds: f64) -> Self {
+        assert!(
+            loop_end_seconds > loop_start_seconds,
+            "loop region must be non-empty: loop_end_seconds ({loop_end_seconds}) must be greater than loop_start_seconds ({loop_start_seconds})"
+        );
+        let rate = f64::from(frames.rate());
+        Self {
+            cursor: Cell::new(0.0),
+            loop_start: loop_start_seconds * rate,
+            loop_end: loop_end_seconds * rate,
+            interpolation: RefCell::new(InterpolationMode::default()),
+            frames,
+        }
+    }
+
+    /// Change the interpolation used to reconstruct samples between stored frames
+    pub fn set_interpolation_mode(&self, mode: InterpolationMode) {
+        *self.interpolation.borrow_mut() = mode;
+    }
+
+    /// Map a (possibly out-of-range) sample index into the loop region
+    ///
+    /// Indices at or past `loop_end` always wrap back to `loop_start`. Indices before
+    /// `loop_start` only wrap (back to the loop's tail) when `in_loop` is set, i.e. when the
+    /// lookup is a neighboring-sample reach from a position that's already inside the loop
+    /// region rather than a legitimate read of the one-shot intro.
+    fn wrap(&self, sample: isize, in_loop: bool) -> isize {
+        let loop_start = self.loop_start as isize;
+        let loop_end = self.loop_end as isize;
+        let len = loop_end - loop_start;
+        if (sample as f64) >= self.loop_end {
+            return loop_start + (sample - loop_end).rem_euclid(len);
+        }
+        if in_loop && sample < loop_start {
+            return loop_start + (sample - loop_start).rem_euclid(len);
+        }
+        sample
+    }
+
+    fn get(&self, sample: isize, in_loop: bool) -> T {
+        let sample = self.wrap(sample, in_loop);
+        if sample < 0 {
+            return T::ZERO;
+        }
+        let sample = sample as usize;
+        if sample >= self.frames.len() {
+            return T::ZERO;
+        }
+        self.frames[sample]
+    }
+}
+
+impl<T: Frame + Copy> Signal for Loop<T> {
+    type Frame = T;
+
+    fn sample(&self, interval: f32, out: &mut [T]) {
+        let ds = f64::from(interval) * f64::from(self.frames.rate());
+        let mode = self.interpolation.borrow().clone();
+        for o in out {
+            let s = self.cursor.get();
+            let x0 = s.trunc() as isize;
+            let frac = s.fract() as f32;
+            let in_loop = s >= self.loop_start;
+            *o = mode
+                .clone()
+                .interpolate(frac, |delta| self.get(x0 + delta, in_loop));
+
+            let next = s + ds;
+            self.cursor.set(if next >= self.loop_end {
+                self.loop_start + (next - self.loop_end) % (self.loop_end - self.loop_start)
+            } else {
+                next
+            });
+        }
+    }
+
+    fn remaining(&self) -> f32 {
+        f32::INFINITY
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,4 +208,51 @@ mod tests {
         s.sample(1.0, &mut buf[2..]);
         assert_eq!(buf, [1.0, 2.0, 3.0, 1.0, 2.0]);
     }
+
+    #[test]
+    fn crossfade_blends_across_seam() {
+        let s = Cycle::with_crossfade(Frames::from_slice(1, FRAMES), 2);
+        let mut buf = [0.0; 3];
+        s.sample(1.0, &mut buf);
+        assert_eq!(buf[0], 1.0);
+        assert_eq!(buf[1], 2.0);
+        // Midway through the fade, tail and head contribute equally under the equal-power curve
+        assert!((buf[2] - 3.535_534).abs() < 1e-4);
+    }
+
+    #[test]
+    fn loop_intro_then_region() {
+        const LOOPED: &[f32] = &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+        let s = Loop::new(Frames::from_slice(1, LOOPED), 2.0, 5.0);
+        let mut buf = [0.0; 8];
+        s.sample(1.0, &mut buf);
+        assert_eq!(buf, [0.0, 1.0, 2.0, 3.0, 4.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn loop_seam_ignores_intro_under_cubic() {
+        // Same loop region (indices 2..5), different intro. Once steady-state looping has
+        // started, wide kernels like `Cubic` must not reach back into the intro for neighbor
+        // samples at the seam, so changing only the intro must not change the looped output.
+        // Half-sample steps keep positions fractional, where the kernel weighs every neighbor.
+        const LOOPED_A: &[f32] = &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+        const LOOPED_B: &[f32] = &[100.0, 200.0, 2.0, 3.0, 4.0, 5.0];
+
+        let run = |data: &[f32]| {
+            let s = Loop::new(Frames::from_slice(1, data), 2.0, 5.0);
+            s.set_interpolation_mode(InterpolationMode::Cubic);
+            let mut buf = [0.0; 12];
+            s.sample(0.5, &mut buf);
+            buf
+        };
+
+        assert_eq!(&run(LOOPED_A)[5..], &run(LOOPED_B)[5..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "loop region must be non-empty")]
+    fn degenerate_loop_region_panics_at_construction() {
+        const LOOPED: &[f32] = &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+        Loop::new(Frames::from_slice(1, LOOPED), 2.0, 2.0);
+    }
 }
@@ -1,13 +1,75 @@
 use std::{
     alloc,
-    cell::Cell,
+    cell::{Cell, RefCell},
+    f32::consts::PI,
     mem,
     ops::{Deref, DerefMut},
     ptr,
     sync::Arc,
 };
 
-use crate::{frame, Frame, Signal};
+use crate::{frame, Frame, PolyphaseFilter, Signal};
+
+/// Method used to reconstruct a signal between its stored samples
+///
+/// Cheaper modes are adequate for audio played near its native rate; modes further down the list
+/// trade CPU time for fewer artifacts when a sound is pitch-shifted or resampled far from the
+/// rate it was recorded at.
+#[derive(Debug, Clone, Default)]
+pub enum InterpolationMode {
+    /// Use the closest sample, with no smoothing
+    Nearest,
+    /// Linearly interpolate between the two closest samples
+    #[default]
+    Linear,
+    /// Interpolate between the two closest samples along a cosine curve, smoothing the slope at
+    /// sample boundaries
+    Cosine,
+    /// Interpolate using a 4-point Catmull-Rom-style cubic kernel for a closer approximation of
+    /// the original band-limited signal
+    Cubic,
+    /// Reconstruct the signal through a windowed-sinc polyphase filter bank, for clean resampling
+    /// between arbitrary sample rates without the aliasing cheaper modes introduce
+    Polyphase(Arc<PolyphaseFilter>),
+}
+
+impl InterpolationMode {
+    /// Reconstruct a frame at fractional offset `frac` past the sample returned by `get(0)`,
+    /// fetching neighboring samples as needed through `get`
+    pub(crate) fn interpolate<T: Frame + Copy>(self, frac: f32, get: impl Fn(isize) -> T) -> T {
+        match self {
+            InterpolationMode::Nearest => get(frac.round() as isize),
+            InterpolationMode::Linear => frame::lerp(&get(0), &get(1), frac),
+            InterpolationMode::Cosine => {
+                let mu2 = (1.0 - (frac * PI).cos()) / 2.0;
+                frame::lerp(&get(0), &get(1), mu2)
+            }
+            InterpolationMode::Cubic => {
+                let y0 = get(-1);
+                let y1 = get(0);
+                let y2 = get(1);
+                let y3 = get(2);
+                let mut result = y1;
+                for i in 0..result.channels().len() {
+                    let (y0, y1, y2, y3) = (
+                        y0.channels()[i],
+                        y1.channels()[i],
+                        y2.channels()[i],
+                        y3.channels()[i],
+                    );
+                    let a0 = -0.5 * y0 + 1.5 * y1 - 1.5 * y2 + 0.5 * y3;
+                    let a1 = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+                    let a2 = -0.5 * y0 + 0.5 * y2;
+                    let a3 = y1;
+                    result.channels_mut()[i] =
+                        a0 * frac.powi(3) + a1 * frac.powi(2) + a2 * frac + a3;
+                }
+                result
+            }
+            InterpolationMode::Polyphase(ref filter) => filter.interpolate(frac, get),
+        }
+    }
+}
 
 /// A sequence of static audio frames at a particular sample rate
 ///
@@ -81,17 +143,18 @@ impl<T: Frame + Copy> Frames<T> {
         self.rate as u32
     }
 
-    /// Interpolate a frame for position `s`
+    /// Interpolate a frame for position `s` using `mode`
     ///
     /// Note that `s` is in samples, not seconds. Whole numbers are always an exact sample, and
     /// out-of-range positions yield 0.
-    pub fn interpolate(&self, s: f64) -> T {
+    ///
+    /// [`InterpolationMode::Polyphase`] is the exception: it filters every position through its
+    /// neighboring samples, so whole numbers need not reproduce the stored sample and positions
+    /// just outside the range can be nonzero.
+    pub fn interpolate(&self, s: f64, mode: InterpolationMode) -> T {
         let x0 = s.trunc() as isize;
-        let fract = s.fract() as f32;
-        let x1 = x0 + 1;
-        let a = self.get(x0);
-        let b = self.get(x1);
-        frame::lerp(&a, &b, fract)
+        let frac = s.fract() as f32;
+        mode.interpolate(frac, |delta| self.get(x0 + delta))
     }
 
     fn get(&self, sample: isize) -> T {
@@ -126,6 +189,8 @@ pub struct FramesSignal<T> {
     data: Arc<Frames<T>>,
     /// Playback position in seconds
     t: Cell<f64>,
+    /// Interpolation used to reconstruct samples between stored frames
+    interpolation: RefCell<InterpolationMode>,
 }
 
 impl<T> FramesSignal<T> {
@@ -135,9 +200,15 @@ impl<T> FramesSignal<T> {
     pub fn new(data: Arc<Frames<T>>, start_seconds: f64) -> Self {
         Self {
             t: Cell::new(start_seconds),
+            interpolation: RefCell::new(InterpolationMode::default()),
             data,
         }
     }
+
+    /// Change the interpolation used to reconstruct samples between stored frames
+    pub fn set_interpolation_mode(&self, mode: InterpolationMode) {
+        *self.interpolation.borrow_mut() = mode;
+    }
 }
 
 impl<T: Frame + Copy> Signal for FramesSignal<T> {
@@ -147,8 +218,9 @@ impl<T: Frame + Copy> Signal for FramesSignal<T> {
     fn sample(&self, interval: f32, out: &mut [T]) {
         let s0 = self.t.get() * self.data.rate;
         let ds = f64::from(interval) * self.data.rate;
+        let mode = self.interpolation.borrow().clone();
         for (i, o) in out.iter_mut().enumerate() {
-            *o = self.data.interpolate(s0 + ds * i as f64);
+            *o = self.data.interpolate(s0 + ds * i as f64, mode.clone());
         }
         self.t
             .set(self.t.get() + f64::from(interval) * out.len() as f64);
@@ -176,4 +248,30 @@ mod tests {
         let frames = Frames::from_slice(1, DATA);
         assert_eq!(&frames[..], DATA);
     }
+
+    #[test]
+    fn nearest_rounds_to_closest_sample() {
+        let frames = Frames::from_slice(1, &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(frames.interpolate(1.25, InterpolationMode::Nearest), 2.0);
+        assert_eq!(frames.interpolate(1.5, InterpolationMode::Nearest), 3.0);
+    }
+
+    #[test]
+    fn cosine_smooths_between_samples() {
+        let frames = Frames::from_slice(1, &[1.0, 2.0, 3.0, 4.0]);
+        // At the midpoint cosine interpolation matches linear...
+        assert!((frames.interpolate(1.5, InterpolationMode::Cosine) - 2.5).abs() < 1e-6);
+        // ...but eases in away from it, unlike linear's constant slope.
+        let eased = frames.interpolate(1.25, InterpolationMode::Cosine);
+        assert!((eased - 2.146_447).abs() < 1e-5);
+    }
+
+    #[test]
+    fn cubic_reproduces_collinear_input_exactly() {
+        // Catmull-Rom is an interpolating spline: for perfectly linear input it must fall back
+        // to the line itself, with no overshoot, at any fractional offset.
+        let frames = Frames::from_slice(1, &[0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert!((frames.interpolate(2.3, InterpolationMode::Cubic) - 2.3).abs() < 1e-5);
+        assert!((frames.interpolate(1.7, InterpolationMode::Cubic) - 1.7).abs() < 1e-5);
+    }
 }
@@ -0,0 +1,109 @@
+use std::f32::consts::PI;
+
+/// A bank of band-limited FIR sub-filters used by [`InterpolationMode::Polyphase`]
+///
+/// Each of the `p` phases holds `n` taps sampled from a windowed sinc, letting playback at an
+/// arbitrary fractional position be reconstructed without the aliasing that cheaper modes
+/// introduce. Cheap to share: build once per sample-rate pair and wrap in an `Arc`.
+///
+/// [`InterpolationMode::Polyphase`]: crate::InterpolationMode::Polyphase
+#[derive(Debug)]
+pub struct PolyphaseFilter {
+    /// Taps per phase
+    n: usize,
+    /// Number of phases
+    p: usize,
+    /// `taps[phase][k]`
+    taps: Box<[Box<[f32]>]>,
+}
+
+impl PolyphaseFilter {
+    /// Build a filter bank for resampling between `source_rate` and `target_rate`, using the
+    /// default `N=16`, `P=64` sizing
+    pub fn new(source_rate: f64, target_rate: f64) -> Self {
+        Self::with_size(16, 64, source_rate, target_rate)
+    }
+
+    /// Build a filter bank with `n` taps per phase and `p` phases
+    pub fn with_size(n: usize, p: usize, source_rate: f64, target_rate: f64) -> Self {
+        let cutoff = (source_rate.min(target_rate) / source_rate) as f32;
+        let taps = (0..p)
+            .map(|phase| {
+                let offset = phase as f32 / p as f32;
+                (0..n)
+                    .map(|k| {
+                        let x = k as f32 - n as f32 / 2.0 - offset;
+                        sinc(x * cutoff) * cutoff * blackman(k, n)
+                    })
+                    .collect::<Vec<_>>()
+                    .into_boxed_slice()
+            })
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+        Self { n, p, taps }
+    }
+
+    /// Reconstruct a frame at fractional offset `frac` past the sample returned by `get(0)`,
+    /// fetching neighboring samples as needed through `get`
+    pub(crate) fn interpolate<T: crate::Frame + Copy>(
+        &self,
+        frac: f32,
+        get: impl Fn(isize) -> T,
+    ) -> T {
+        let rounded = (frac * self.p as f32).round() as usize;
+        // Rounding can carry into a full sample (e.g. frac near 1.0 with p=64 rounds to phase
+        // p): fold that into the next integer sample at phase 0 rather than wrapping back to
+        // phase 0 here, which would replay the current sample instead of advancing.
+        let (base, q) = (rounded / self.p, rounded % self.p);
+        let taps = &self.taps[q];
+        let half = self.n as isize / 2;
+        let mut acc = T::ZERO;
+        for (k, &tap) in taps.iter().enumerate() {
+            let sample = get(base as isize + k as isize - half);
+            for i in 0..acc.channels().len() {
+                acc.channels_mut()[i] += sample.channels()[i] * tap;
+            }
+        }
+        acc
+    }
+}
+
+/// Normalized sinc function: `sin(pi*x) / (pi*x)`, with `sinc(0) = 1`
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-7 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Blackman window, used to taper the sinc so the truncated filter has fewer ripples
+fn blackman(k: usize, n: usize) -> f32 {
+    let a0 = 0.42;
+    let a1 = 0.5;
+    let a2 = 0.08;
+    let x = 2.0 * PI * k as f32 / (n - 1) as f32;
+    a0 - a1 * x.cos() + a2 * (2.0 * x).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_size_builds_n_taps_per_p_phases() {
+        let filter = PolyphaseFilter::with_size(4, 8, 1.0, 1.0);
+        assert_eq!(filter.taps.len(), 8);
+        assert!(filter.taps.iter().all(|phase| phase.len() == 4));
+    }
+
+    #[test]
+    fn phase_rounding_up_to_p_advances_the_base_sample() {
+        // With p=4, a frac of 0.9 rounds to phase 4 -- a full sample's worth of phases -- which
+        // must be treated as phase 0 one sample later, not phase 0 at the current sample.
+        let filter = PolyphaseFilter::with_size(4, 4, 1.0, 1.0);
+        let near_next_sample: f32 = filter.interpolate(0.9, |i| i as f32);
+        let next_sample_phase_zero: f32 = filter.interpolate(0.0, |i| (i + 1) as f32);
+        assert_eq!(near_next_sample, next_sample_phase_zero);
+    }
+}
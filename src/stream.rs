@@ -0,0 +1,127 @@
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{Controlled, Frame, Signal};
+
+/// A signal fed incrementally by a producer thread, for audio decoded on the fly
+///
+/// Frames are pushed into a fixed-capacity ring buffer through a [`StreamControl`]; `sample`
+/// drains that buffer as the mixer pulls audio. If the buffer underruns, silence is emitted
+/// rather than treating the stream as finished, so a producer that falls briefly behind doesn't
+/// cause an audible stop.
+pub struct Stream<T> {
+    rate: u32,
+    /// Ring buffer of queued frames, indexed modulo `buffer.len()`
+    buffer: Box<[Cell<T>]>,
+    /// Index of the next frame to read
+    read: AtomicUsize,
+    /// Index of the next frame to write
+    write: AtomicUsize,
+}
+
+// SAFETY: `read` is only ever advanced by the consumer (in `sample`) and `write` only by the
+// producer (in `StreamControl::push`), and each reads the other's index before touching a cell
+// that index guards, so the two sides never access the same cell concurrently.
+unsafe impl<T: Send> Sync for Stream<T> {}
+
+impl<T: Frame + Copy> Stream<T> {
+    /// Construct a stream at `rate` samples/sec with room to buffer `capacity` frames
+    pub(crate) fn new(rate: u32, capacity: usize) -> Self {
+        Self {
+            rate,
+            buffer: (0..capacity).map(|_| Cell::new(T::ZERO)).collect(),
+            read: AtomicUsize::new(0),
+            write: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<T: Frame + Copy> Signal for Stream<T> {
+    type Frame = T;
+
+    fn sample(&self, _interval: f32, out: &mut [T]) {
+        let mut read = self.read.load(Ordering::Relaxed);
+        let write = self.write.load(Ordering::Acquire);
+        for o in out {
+            *o = if read == write {
+                T::ZERO
+            } else {
+                let frame = self.buffer[read % self.buffer.len()].get();
+                read = read.wrapping_add(1);
+                frame
+            };
+        }
+        self.read.store(read, Ordering::Release);
+    }
+
+    fn remaining(&self) -> f32 {
+        let read = self.read.load(Ordering::Relaxed);
+        let write = self.write.load(Ordering::Acquire);
+        write.wrapping_sub(read) as f32 / self.rate as f32
+    }
+}
+
+/// Thread-safe control for a [`Stream`] signal
+///
+/// Not `Clone`: `push` advances `Stream::write` with a plain load-then-store rather than an
+/// atomic read-modify-write, which is only sound with a single producer. Route all pushes for a
+/// given stream through one `StreamControl`.
+pub struct StreamControl<'a, T>(&'a Stream<T>);
+
+unsafe impl<'a, T: 'a> Controlled<'a> for Stream<T> {
+    type Control = StreamControl<'a, T>;
+
+    unsafe fn make_control(signal: &'a Stream<T>) -> Self::Control {
+        StreamControl(signal)
+    }
+}
+
+impl<'a, T: Frame + Copy> StreamControl<'a, T> {
+    /// Enqueue `frames`, returning how many were accepted before the buffer filled up
+    pub fn push(&self, frames: &[T]) -> usize {
+        let read = self.0.read.load(Ordering::Acquire);
+        let mut write = self.0.write.load(Ordering::Relaxed);
+        let capacity = self.0.buffer.len();
+        let free = capacity - write.wrapping_sub(read);
+        let n = frames.len().min(free);
+        for &frame in &frames[..n] {
+            self.0.buffer[write % capacity].set(frame);
+            write = write.wrapping_add(1);
+        }
+        self.0.write.store(write, Ordering::Release);
+        n
+    }
+
+    /// Number of frames that can currently be queued without being dropped
+    pub fn free_capacity(&self) -> usize {
+        let read = self.0.read.load(Ordering::Acquire);
+        let write = self.0.write.load(Ordering::Relaxed);
+        self.0.buffer.len() - write.wrapping_sub(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_then_drain() {
+        let stream = Stream::<f32>::new(1, 4);
+        let control = unsafe { Stream::make_control(&stream) };
+        assert_eq!(control.push(&[1.0, 2.0, 3.0]), 3);
+        let mut buf = [0.0; 4];
+        stream.sample(1.0, &mut buf);
+        assert_eq!(buf, [1.0, 2.0, 3.0, 0.0]);
+    }
+
+    #[test]
+    fn push_respects_free_capacity() {
+        let stream = Stream::<f32>::new(1, 2);
+        let control = unsafe { Stream::make_control(&stream) };
+        assert_eq!(control.free_capacity(), 2);
+        assert_eq!(control.push(&[1.0, 2.0, 3.0]), 2);
+        assert_eq!(control.free_capacity(), 0);
+    }
+}